@@ -0,0 +1,156 @@
+use std::ffi::CString;
+use std::mem;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::os::raw::{c_int, c_void};
+
+use libc::{dlopen, dlsym, ifaddrs, sockaddr_in, sockaddr_in6, strlen, RTLD_LAZY, AF_INET, AF_INET6};
+use once_cell::sync::OnceCell;
+
+use crate::netlink;
+use crate::Error;
+
+/// `ifaddrs` struct raw pointer alias
+type IfAddrsPtr = *mut *mut ifaddrs;
+
+type GetIfAddrsFn = unsafe extern "C" fn(IfAddrsPtr) -> c_int;
+type FreeIfAddrsFn = unsafe extern "C" fn(*mut ifaddrs);
+
+/// `getifaddrs`/`freeifaddrs` symbols resolved at runtime from `libc.so`
+///
+/// Older Android NDKs (minimum SDK below 24) don't declare these symbols in
+/// `ifaddrs.h`, so linking against them directly is not possible. They are
+/// still present on-device for most API levels, so they are resolved lazily
+/// through `dlopen`/`dlsym` instead.
+struct LibcIfAddrs {
+    getifaddrs: GetIfAddrsFn,
+    freeifaddrs: FreeIfAddrsFn,
+}
+
+// The resolved function pointers are plain data and safe to share across
+// threads, `OnceCell` just needs the assurance spelled out.
+unsafe impl Send for LibcIfAddrs {}
+unsafe impl Sync for LibcIfAddrs {}
+
+static LIBC_IFADDRS: OnceCell<Option<LibcIfAddrs>> = OnceCell::new();
+
+/// Resolves `getifaddrs`/`freeifaddrs` from `libc.so`, caching the result for
+/// the lifetime of the process. Returns `None` when either symbol could not
+/// be found, in which case callers should fall back to the netlink path.
+fn dynamic_ifaddrs() -> Option<&'static LibcIfAddrs> {
+    LIBC_IFADDRS
+        .get_or_init(|| unsafe {
+            let lib_name = CString::new("libc.so").ok()?;
+            let handle = dlopen(lib_name.as_ptr(), RTLD_LAZY);
+
+            if handle.is_null() {
+                return None;
+            }
+
+            let getifaddrs = resolve_symbol(handle, "getifaddrs")?;
+            let freeifaddrs = resolve_symbol(handle, "freeifaddrs")?;
+
+            Some(LibcIfAddrs {
+                getifaddrs,
+                freeifaddrs,
+            })
+        })
+        .as_ref()
+}
+
+unsafe fn resolve_symbol<T: Copy>(handle: *mut c_void, name: &str) -> Option<T> {
+    let symbol_name = CString::new(name).ok()?;
+    let symbol = dlsym(handle, symbol_name.as_ptr());
+
+    if symbol.is_null() {
+        return None;
+    }
+
+    Some(mem::transmute_copy(&symbol))
+}
+
+/// Perform a search over the system's network interfaces.
+///
+/// On Android this first tries `getifaddrs` resolved dynamically from
+/// `libc.so`, and when that symbol is unavailable on the running device,
+/// falls back to [`crate::netlink::list_afinet_netlink`], so the rest of the
+/// crate sees the same `Vec<(String, IpAddr)>` either way.
+pub fn find_af_inet() -> Result<Vec<(String, IpAddr)>, Error> {
+    match dynamic_ifaddrs() {
+        Some(libc_ifaddrs) => find_af_inet_dynamic(libc_ifaddrs),
+        None => netlink::list_afinet_netlink(),
+    }
+}
+
+fn find_af_inet_dynamic(libc_ifaddrs: &LibcIfAddrs) -> Result<Vec<(String, IpAddr)>, Error> {
+    let ifaddrs_size = mem::size_of::<IfAddrsPtr>();
+
+    unsafe {
+        let myaddr: IfAddrsPtr = libc::malloc(ifaddrs_size) as IfAddrsPtr;
+        let getifaddrs_result = (libc_ifaddrs.getifaddrs)(myaddr);
+
+        if getifaddrs_result != 0 {
+            libc::free(myaddr as *mut c_void);
+            return Err(Error::GetIfAddrsError(getifaddrs_result));
+        }
+
+        // `myaddr` only ever holds the head pointer `getifaddrs` wrote into
+        // it; the walk below must use its own cursor so `head` is still
+        // intact when it's handed to `freeifaddrs`.
+        let head = *myaddr;
+        let result = walk_ifaddrs(head);
+
+        (libc_ifaddrs.freeifaddrs)(head);
+        libc::free(myaddr as *mut c_void);
+
+        result
+    }
+}
+
+unsafe fn walk_ifaddrs(head: *mut ifaddrs) -> Result<Vec<(String, IpAddr)>, Error> {
+    let mut interfaces: Vec<(String, IpAddr)> = Vec::new();
+    let mut cursor = head;
+
+    while !cursor.is_null() {
+        let ifa_addr = (*cursor).ifa_addr;
+
+        match (*ifa_addr).sa_family as i32 {
+            AF_INET => {
+                let socket_addr_v4: *mut sockaddr_in = ifa_addr as *mut sockaddr_in;
+                let in_addr = (*socket_addr_v4).sin_addr;
+                let mut ip_addr = Ipv4Addr::from(in_addr.s_addr);
+
+                if cfg!(target_endian = "little") {
+                    ip_addr = Ipv4Addr::from(in_addr.s_addr.swap_bytes());
+                }
+
+                let name = get_ifa_name(cursor)?;
+
+                interfaces.push((name, IpAddr::V4(ip_addr)));
+            }
+            AF_INET6 => {
+                let socket_addr_v6: *mut sockaddr_in6 = ifa_addr as *mut sockaddr_in6;
+                let in6_addr = (*socket_addr_v6).sin6_addr;
+                let ip_addr = Ipv6Addr::from(in6_addr.s6_addr);
+                let name = get_ifa_name(cursor)?;
+
+                interfaces.push((name, IpAddr::V6(ip_addr)));
+            }
+            _ => {}
+        }
+
+        cursor = (*cursor).ifa_next;
+    }
+
+    Ok(interfaces)
+}
+
+/// Retrieves the name of a interface address
+unsafe fn get_ifa_name(ifa: *mut ifaddrs) -> Result<String, Error> {
+    let str = (*ifa).ifa_name as *mut u8;
+    let len = strlen(str as *const i8);
+    let slice = std::slice::from_raw_parts(str, len);
+    match String::from_utf8(slice.to_vec()) {
+        Ok(s) => Ok(s),
+        Err(_e) => Err(Error::IntAddrNameParseError(_e)),
+    }
+}
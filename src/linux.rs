@@ -1,100 +1,224 @@
 use std::mem;
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
-use neli::attr::Attribute;
-use neli::consts::nl::{NlmF, NlmFFlags};
-use neli::consts::socket::NlFamily;
-use neli::consts::rtnl::{Ifa, IfaFFlags, RtAddrFamily, RtScope, Rtm};
-use neli::nl::{NlPayload, Nlmsghdr};
-use neli::rtnl::Ifaddrmsg;
-use neli::socket::NlSocketHandle;
-use neli::types::RtBuffer;
-use libc::{getifaddrs, ifaddrs, sockaddr_in, sockaddr_in6, strlen, AF_INET, AF_INET6};
+use neli::consts::rtnl::RtScope;
+use libc::{getifaddrs, ifaddrs, sockaddr, sockaddr_in, sockaddr_in6, strlen, AF_INET, AF_INET6};
 
+use crate::netlink::{self, netlink_addrs};
 use crate::Error;
 
-fn make_ifaddrmsg() -> Ifaddrmsg {
-    Ifaddrmsg {
-        ifa_family: RtAddrFamily::Inet,
-        ifa_prefixlen: 0,
-        ifa_flags: IfaFFlags::empty(),
-        ifa_scope: 0,
-        ifa_index: 0,
-        rtattrs: RtBuffer::new(),
-    }
+/// IPv4 details of a network interface address
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ifv4Addr {
+    pub ip: Ipv4Addr,
+    pub netmask: Option<Ipv4Addr>,
+    pub broadcast: Option<Ipv4Addr>,
+}
+
+/// IPv6 details of a network interface address
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ifv6Addr {
+    pub ip: Ipv6Addr,
+    pub netmask: Option<Ipv6Addr>,
+    pub scope_id: Option<u32>,
+}
+
+/// Address family specific details of a network interface address
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IfAddr {
+    V4(Ifv4Addr),
+    V6(Ifv6Addr),
 }
 
-fn make_netlink_message(ifaddrmsg: NlPayload<Ifaddrmsg>) -> Nlmsghdr<Rtm, NlPayload<Ifaddrmsg>> {
-    Nlmsghdr::new(
-        None,
-        Rtm::Getaddr,
-        NlmFFlags::new(&[NlmF::Request, NlmF::Root]),
-        None,
-        None,
-        NlPayload::Payload(ifaddrmsg),
-    )
+/// A network interface address, as returned by [`find_interfaces`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Interface {
+    pub name: String,
+    pub addr: IfAddr,
+    /// Raw `ifa_flags` as reported by `getifaddrs`, e.g. `IFF_LOOPBACK`,
+    /// `IFF_POINTOPOINT` or `IFF_BROADCAST`.
+    pub flags: u32,
 }
 
 /// Retrieves the local IP address fo this system
 pub fn local_ip() -> Result<IpAddr, Error> {
-    let mut netlink_socket = NlSocketHandle::connect(NlFamily::Route, None, &[])
-        .map_err(|err| Error::NetlinkIOError(err.to_string()))?;
-    let ifaddrmsg = make_ifaddrmsg();
-    let netlink_payload = NlPayload::Payload(ifaddrmsg);
-    let netlink_message = make_netlink_message(netlink_payload);
+    netlink_addrs()?
+        .into_iter()
+        .find(|entry| entry.scope == RtScope::Universe && matches!(entry.addr, IpAddr::V4(_)))
+        .map(|entry| entry.addr)
+        .ok_or(Error::NetlinkFailedToFindLocalIp)
+}
 
-    netlink_socket
-        .send(netlink_message)
-        .map_err(|err| Error::NetlinkSendMessageError(err.to_string()))?;
+/// Retrieves the local IPv6 address of this system
+pub fn local_ipv6() -> Result<IpAddr, Error> {
+    netlink_addrs()?
+        .into_iter()
+        .find(|entry| entry.scope == RtScope::Universe && matches!(entry.addr, IpAddr::V6(_)))
+        .map(|entry| entry.addr)
+        .ok_or(Error::NetlinkFailedToFindLocalIp)
+}
 
-    let mut addrs = Vec::<Ipv4Addr>::with_capacity(1);
+/// Enumerates every address from a single `RTM_GETADDR` netlink dump,
+/// producing the same `Vec<(String, IpAddr)>` shape as [`find_af_inet`]
+/// without depending on libc's `getifaddrs` at all.
+///
+/// See [`crate::netlink::list_afinet_netlink`] for how interface names are
+/// resolved.
+pub fn list_afinet_netlink() -> Result<Vec<(String, IpAddr)>, Error> {
+    netlink::list_afinet_netlink()
+}
 
-    for response in netlink_socket.iter(false) {
-        let header: Nlmsghdr<_, Ifaddrmsg> =
-            response.map_err(|_| Error::NetlinkFailedToFindLocalIp)?;
+/// `ifaddrs` struct raw pointer alias
+type IfAddrsPtr = *mut *mut ifaddrs;
 
-        if let NlPayload::Empty = header.nl_payload {
-            continue;
-        }
+/// Perform a search over the system's network interfaces using `getifaddrs`,
+/// retrieving network interfaces belonging to both socket address families
+/// `AF_INET` and `AF_INET6` along with their netmask, broadcast address,
+/// `ifa_flags` and (for IPv6) scope id.
+pub fn find_interfaces() -> Result<Vec<Interface>, Error> {
+    let ifaddrs_size = mem::size_of::<IfAddrsPtr>();
+
+    unsafe {
+        let myaddr: IfAddrsPtr = libc::malloc(ifaddrs_size) as IfAddrsPtr;
+        let getifaddrs_result = getifaddrs(myaddr);
 
-        if header.nl_type != Rtm::Newaddr.into() {
-            return Err(Error::NetlinkFailedToFindLocalIp);
+        if getifaddrs_result != 0 {
+            // an error ocurred on getifaddrs
+            libc::free(myaddr as *mut libc::c_void);
+            return Err(Error::GetIfAddrsError(getifaddrs_result));
         }
 
-        let p = header
-            .get_payload()
-            .map_err(|_| Error::NetlinkFailedToFindLocalIp)?;
+        let head = *myaddr;
+        let result = walk_ifaddrs(head);
 
-        if RtScope::from(p.ifa_scope) != RtScope::Universe {
-            continue;
-        }
+        // Release both the linked list `getifaddrs` allocated and the
+        // pointer-to-pointer cell used to receive its head, regardless of
+        // whether the walk above succeeded: `find_interfaces()` is called on
+        // every `local_ip_for()` lookup, so leaking here leaks on every call.
+        libc::freeifaddrs(head);
+        libc::free(myaddr as *mut libc::c_void);
 
-        for rtattr in p.rtattrs.iter() {
-            if rtattr.rta_type == Ifa::Local {
-                addrs.push(Ipv4Addr::from(u32::from_be(
-                    rtattr
-                        .get_payload_as::<u32>()
-                        .map_err(|_| Error::NetlinkFailedToFindLocalIp)?,
-                )));
+        result
+    }
+}
+
+/// Walks the `ifaddrs` linked list starting at `head`, collecting an
+/// [`Interface`] for every `AF_INET`/`AF_INET6` node. An instance of
+/// `ifaddrs` is built on top of a linked list where `ifaddrs.ifa_next`
+/// represents the next node in the list; every node is processed
+/// unconditionally and the walk stops once the node whose `ifa_next` is null
+/// has been handled, so the last entry isn't silently dropped.
+unsafe fn walk_ifaddrs(head: *mut ifaddrs) -> Result<Vec<Interface>, Error> {
+    let mut interfaces: Vec<Interface> = Vec::new();
+    let mut cursor = head;
+
+    loop {
+        let ifa_addr = (*cursor).ifa_addr;
+        let flags = (*cursor).ifa_flags;
+
+        match (*ifa_addr).sa_family as i32 {
+            // AF_INET IPv4 protocol implementation
+            AF_INET => {
+                let interface_address = ifa_addr;
+                let socket_addr_v4: *mut sockaddr_in = interface_address as *mut sockaddr_in;
+                let in_addr = (*socket_addr_v4).sin_addr;
+                let mut ip = Ipv4Addr::from(in_addr.s_addr);
+
+                if cfg!(target_endian = "little") {
+                    // due to a difference on how bytes are arranged on a
+                    // single word of memory by the CPU, swap bytes based
+                    // on CPU endianess to avoid having twisted IP addresses
+                    //
+                    // refer: https://github.com/rust-lang/rust/issues/48819
+                    ip = Ipv4Addr::from(in_addr.s_addr.swap_bytes());
+                }
+
+                let netmask = read_ipv4_sockaddr((*cursor).ifa_netmask);
+                let broadcast = read_ipv4_sockaddr((*cursor).ifa_ifu);
+                let name = get_ifa_name(cursor)?;
+
+                interfaces.push(Interface {
+                    name,
+                    addr: IfAddr::V4(Ifv4Addr {
+                        ip,
+                        netmask,
+                        broadcast,
+                    }),
+                    flags,
+                });
             }
+            // AF_INET6 IPv6 protocol implementation
+            AF_INET6 => {
+                let interface_address = ifa_addr;
+                let socket_addr_v6: *mut sockaddr_in6 = interface_address as *mut sockaddr_in6;
+                let in6_addr = (*socket_addr_v6).sin6_addr;
+                let ip = Ipv6Addr::from(in6_addr.s6_addr);
+                let scope_id = match (*socket_addr_v6).sin6_scope_id {
+                    0 => None,
+                    scope_id => Some(scope_id),
+                };
+                let netmask = read_ipv6_sockaddr((*cursor).ifa_netmask);
+                let name = get_ifa_name(cursor)?;
+
+                interfaces.push(Interface {
+                    name,
+                    addr: IfAddr::V6(Ifv6Addr {
+                        ip,
+                        netmask,
+                        scope_id,
+                    }),
+                    flags,
+                });
+            }
+            _ => {}
+        }
+
+        if (*cursor).ifa_next.is_null() {
+            break;
         }
+
+        cursor = (*cursor).ifa_next;
     }
 
-    if let Some(local_ip) = addrs.first() {
-        let ipaddr = IpAddr::V4(local_ip.to_owned());
+    Ok(interfaces)
+}
 
-        return Ok(ipaddr);
+/// Reads an `AF_INET` `sockaddr` pointer (as found in `ifa_netmask`/`ifa_ifu`)
+/// into an [`Ipv4Addr`], returning `None` when the pointer is null, which
+/// `getifaddrs` uses to mean "not available for this interface".
+unsafe fn read_ipv4_sockaddr(sockaddr: *mut sockaddr) -> Option<Ipv4Addr> {
+    if sockaddr.is_null() {
+        return None;
     }
 
-    Err(Error::NetlinkFailedToFindLocalIp)
+    let socket_addr_v4 = sockaddr as *mut sockaddr_in;
+    let in_addr = (*socket_addr_v4).sin_addr;
+
+    if cfg!(target_endian = "little") {
+        Some(Ipv4Addr::from(in_addr.s_addr.swap_bytes()))
+    } else {
+        Some(Ipv4Addr::from(in_addr.s_addr))
+    }
 }
 
-/// `ifaddrs` struct raw pointer alias
-type IfAddrsPtr = *mut *mut ifaddrs;
+/// Reads an `AF_INET6` `sockaddr` pointer (as found in `ifa_netmask`) into an
+/// [`Ipv6Addr`], returning `None` when the pointer is null.
+unsafe fn read_ipv6_sockaddr(sockaddr: *mut sockaddr) -> Option<Ipv6Addr> {
+    if sockaddr.is_null() {
+        return None;
+    }
+
+    let socket_addr_v6 = sockaddr as *mut sockaddr_in6;
+
+    Some(Ipv6Addr::from((*socket_addr_v6).sin6_addr.s6_addr))
+}
 
 /// Perform a search over the system's network interfaces using `getifaddrs`,
 /// retrieved network interfaces belonging to both socket address families
 /// `AF_INET` and `AF_INET6` are retrieved along with the interface address name.
 ///
+/// This is a compatibility wrapper around [`find_interfaces`] for callers
+/// that only care about the name and address of each interface.
+///
 /// # Example
 ///
 /// ```
@@ -111,84 +235,287 @@ type IfAddrsPtr = *mut *mut ifaddrs;
 /// }
 /// ```
 pub fn find_af_inet() -> Result<Vec<(String, IpAddr)>, Error> {
-    let ifaddrs_size = mem::size_of::<IfAddrsPtr>();
+    let interfaces = find_interfaces()?;
 
-    unsafe {
-        let myaddr: IfAddrsPtr = libc::malloc(ifaddrs_size) as IfAddrsPtr;
-        let getifaddrs_result = getifaddrs(myaddr);
-
-        if getifaddrs_result != 0 {
-            // an error ocurred on getifaddrs
-            return Err(Error::GetIfAddrsError(getifaddrs_result));
-        }
-
-        let mut interfaces: Vec<(String, IpAddr)> = Vec::new();
-        let ifa = myaddr;
-
-        // An instance of `ifaddrs` is build on top of a linked list where
-        // `ifaddrs.ifa_next` represent the next node in the list.
-        //
-        // To find the relevant interface address walk over the nodes of the
-        // linked list looking for interface address which belong to the socket
-        // address families AF_INET (IPv4) and AF_INET6 (IPv6)
-        while !(**ifa).ifa_next.is_null() {
-            let ifa_addr = (**ifa).ifa_addr;
-
-            match (*ifa_addr).sa_family as i32 {
-                // AF_INET IPv4 protocol implementation
-                AF_INET => {
-                    let interface_address = ifa_addr;
-                    let socket_addr_v4: *mut sockaddr_in = interface_address as *mut sockaddr_in;
-                    let in_addr = (*socket_addr_v4).sin_addr;
-                    let mut ip_addr = Ipv4Addr::from(in_addr.s_addr);
-
-                    if cfg!(target_endian = "little") {
-                        // due to a difference on how bytes are arranged on a
-                        // single word of memory by the CPU, swap bytes based
-                        // on CPU endianess to avoid having twisted IP addresses
-                        //
-                        // refer: https://github.com/rust-lang/rust/issues/48819
-                        ip_addr = Ipv4Addr::from(in_addr.s_addr.swap_bytes());
-                    }
-
-                    let name = get_ifa_name(ifa)?;
-
-                    interfaces.push((name, IpAddr::V4(ip_addr)));
-
-                    *ifa = (**ifa).ifa_next;
-                    continue;
-                }
-                // AF_INET6 IPv6 protocol implementation
-                AF_INET6 => {
-                    let interface_address = ifa_addr;
-                    let socket_addr_v6: *mut sockaddr_in6 = interface_address as *mut sockaddr_in6;
-                    let in6_addr = (*socket_addr_v6).sin6_addr;
-                    let ip_addr = Ipv6Addr::from(in6_addr.s6_addr);
-                    let name = get_ifa_name(ifa)?;
-
-                    interfaces.push((name, IpAddr::V6(ip_addr)));
-
-                    *ifa = (**ifa).ifa_next;
-                    continue;
-                }
-                _ => {
-                    *ifa = (**ifa).ifa_next;
-                    continue;
-                }
-            }
-        }
+    Ok(interfaces
+        .into_iter()
+        .map(|interface| {
+            let ip = match interface.addr {
+                IfAddr::V4(ifv4) => IpAddr::V4(ifv4.ip),
+                IfAddr::V6(ifv6) => IpAddr::V6(ifv6.ip),
+            };
 
-        Ok(interfaces)
-    }
+            (interface.name, ip)
+        })
+        .collect())
 }
 
 /// Retrieves the name of a interface address
-unsafe fn get_ifa_name(ifa: *mut *mut ifaddrs) -> Result<String, Error> {
-    let str = (*(*ifa)).ifa_name as *mut u8;
+unsafe fn get_ifa_name(ifa: *mut ifaddrs) -> Result<String, Error> {
+    let str = (*ifa).ifa_name as *mut u8;
     let len = strlen(str as *const i8);
     let slice = std::slice::from_raw_parts(str, len);
     match String::from_utf8(slice.to_vec()) {
         Ok(s) => Ok(s),
         Err(_e) => Err(Error::IntAddrNameParseError(_e)),
     }
+}
+
+/// Picks the local source address this system would likely use to reach
+/// `dest`, following the RFC 3484 longest-matching-prefix rule for source
+/// address selection.
+///
+/// Candidates are enumerated with [`find_interfaces`]. Candidates of a
+/// different address family, or of a narrower scope than `dest`, are
+/// discarded. Among the remaining candidates the one sharing the longest
+/// common prefix with `dest` wins; ties are broken by preferring an
+/// exact-subnet match, then global scope over link-local, and finally by
+/// keeping the first match in enumeration order.
+pub fn local_ip_for(dest: IpAddr) -> Result<IpAddr, Error> {
+    let dest = normalize_ipv4_mapped(dest);
+
+    let mut best: Option<Candidate> = None;
+
+    for interface in find_interfaces()? {
+        let (addr, prefixlen) = match interface.addr {
+            IfAddr::V4(ifv4) => (
+                IpAddr::V4(ifv4.ip),
+                ifv4.netmask.map(|mask| u32::from(mask).count_ones()),
+            ),
+            IfAddr::V6(ifv6) => (
+                IpAddr::V6(ifv6.ip),
+                ifv6
+                    .netmask
+                    .map(|mask| mask.octets().iter().map(|byte| byte.count_ones()).sum()),
+            ),
+        };
+
+        if mem::discriminant(&addr) != mem::discriminant(&dest) {
+            continue;
+        }
+
+        let scope = scope_rank(&addr);
+
+        if scope < scope_rank(&dest) {
+            continue;
+        }
+
+        let common_prefix_len = common_prefix_len(addr, dest);
+        let exact_subnet = prefixlen.is_some_and(|prefixlen| common_prefix_len >= prefixlen);
+
+        let candidate = Candidate {
+            addr,
+            common_prefix_len,
+            exact_subnet,
+            scope,
+        };
+
+        let is_better = match &best {
+            None => true,
+            Some(current_best) => is_better_candidate(&candidate, current_best),
+        };
+
+        if is_better {
+            best = Some(candidate);
+        }
+    }
+
+    best.map(|candidate| candidate.addr)
+        .ok_or(Error::LocalIpAddressNotFound)
+}
+
+/// A candidate source address considered by [`local_ip_for`], together with
+/// the derived ranking fields needed to compare it against another
+/// candidate without re-deriving them.
+struct Candidate {
+    addr: IpAddr,
+    common_prefix_len: u32,
+    exact_subnet: bool,
+    scope: u8,
+}
+
+/// Ranks `candidate` against `current_best` using the RFC 3484 tie-break
+/// order: longest common prefix with the destination first, then an
+/// exact-subnet match, then global scope over link-local.
+fn is_better_candidate(candidate: &Candidate, current_best: &Candidate) -> bool {
+    if candidate.common_prefix_len != current_best.common_prefix_len {
+        candidate.common_prefix_len > current_best.common_prefix_len
+    } else if candidate.exact_subnet != current_best.exact_subnet {
+        candidate.exact_subnet
+    } else {
+        candidate.scope > current_best.scope
+    }
+}
+
+/// Converts an IPv4-mapped IPv6 address (`::ffff:a.b.c.d`) into its IPv4
+/// form so it can be compared against IPv4 candidates.
+fn normalize_ipv4_mapped(addr: IpAddr) -> IpAddr {
+    match addr {
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ipv4) => IpAddr::V4(ipv4),
+            None => IpAddr::V6(ip),
+        },
+        other => other,
+    }
+}
+
+/// A coarse address scope ranking used to implement the RFC 3484 rule that
+/// a source address must not be of a narrower scope than the destination.
+fn scope_rank(addr: &IpAddr) -> u8 {
+    match addr {
+        IpAddr::V4(ip) => {
+            if ip.is_loopback() {
+                0
+            } else if ip.is_link_local() {
+                1
+            } else {
+                2
+            }
+        }
+        IpAddr::V6(ip) => {
+            if ip.is_loopback() {
+                0
+            } else if (ip.segments()[0] & 0xffc0) == 0xfe80 {
+                1
+            } else {
+                2
+            }
+        }
+    }
+}
+
+/// Computes the number of leading bits shared between two addresses of the
+/// same family, by XORing their big-endian byte representation and counting
+/// leading zero bits: whole zero bytes count as 8 bits each, then
+/// `leading_zeros()` accounts for the first differing byte.
+fn common_prefix_len(a: IpAddr, b: IpAddr) -> u32 {
+    match (a, b) {
+        (IpAddr::V4(a), IpAddr::V4(b)) => common_prefix_len_bytes(&a.octets(), &b.octets()),
+        (IpAddr::V6(a), IpAddr::V6(b)) => common_prefix_len_bytes(&a.octets(), &b.octets()),
+        _ => 0,
+    }
+}
+
+fn common_prefix_len_bytes(a: &[u8], b: &[u8]) -> u32 {
+    let mut len = 0;
+
+    for (x, y) in a.iter().zip(b.iter()) {
+        let xor = x ^ y;
+
+        if xor == 0 {
+            len += 8;
+            continue;
+        }
+
+        len += xor.leading_zeros();
+        break;
+    }
+
+    len
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn common_prefix_len_bytes_counts_whole_bytes_then_leading_zeros() {
+        assert_eq!(common_prefix_len_bytes(&[10, 0, 1, 5], &[10, 0, 1, 5]), 32);
+        assert_eq!(common_prefix_len_bytes(&[10, 0, 0, 0], &[10, 0, 255, 0]), 16);
+        // 0b0000_0001 ^ 0b0000_0010 == 0b0000_0011, 6 leading zero bits
+        assert_eq!(common_prefix_len_bytes(&[1], &[2]), 6);
+    }
+
+    #[test]
+    fn scope_rank_orders_loopback_link_local_and_global() {
+        let loopback = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
+        let link_local = IpAddr::V4(Ipv4Addr::new(169, 254, 0, 1));
+        let global = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+
+        assert!(scope_rank(&loopback) < scope_rank(&link_local));
+        assert!(scope_rank(&link_local) < scope_rank(&global));
+
+        let loopback_v6 = IpAddr::V6(Ipv6Addr::LOCALHOST);
+        let link_local_v6 = IpAddr::V6(Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 1));
+        let global_v6 = IpAddr::V6(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1));
+
+        assert!(scope_rank(&loopback_v6) < scope_rank(&link_local_v6));
+        assert!(scope_rank(&link_local_v6) < scope_rank(&global_v6));
+    }
+
+    #[test]
+    fn normalize_ipv4_mapped_unwraps_v4_mapped_v6() {
+        let mapped = IpAddr::V6(Ipv4Addr::new(192, 0, 2, 1).to_ipv6_mapped());
+
+        assert_eq!(
+            normalize_ipv4_mapped(mapped),
+            IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1))
+        );
+
+        let untouched = IpAddr::V6(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1));
+        assert_eq!(normalize_ipv4_mapped(untouched), untouched);
+
+        let v4 = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1));
+        assert_eq!(normalize_ipv4_mapped(v4), v4);
+    }
+
+    #[test]
+    fn longest_common_prefix_wins_over_exact_subnet_match() {
+        // Candidate A is an exact match for its own /24 but shares fewer
+        // bits with the destination than candidate B, which isn't exact for
+        // its /30. The longest common prefix must win regardless.
+        let a = Candidate {
+            addr: IpAddr::V4(Ipv4Addr::new(10, 0, 1, 5)),
+            common_prefix_len: 24,
+            exact_subnet: true,
+            scope: 2,
+        };
+        let b = Candidate {
+            addr: IpAddr::V4(Ipv4Addr::new(10, 0, 2, 9)),
+            common_prefix_len: 28,
+            exact_subnet: false,
+            scope: 2,
+        };
+
+        assert!(is_better_candidate(&b, &a));
+        assert!(!is_better_candidate(&a, &b));
+    }
+
+    #[test]
+    fn exact_subnet_breaks_ties_on_common_prefix_len() {
+        let exact = Candidate {
+            addr: IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)),
+            common_prefix_len: 24,
+            exact_subnet: true,
+            scope: 2,
+        };
+        let not_exact = Candidate {
+            addr: IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2)),
+            common_prefix_len: 24,
+            exact_subnet: false,
+            scope: 2,
+        };
+
+        assert!(is_better_candidate(&exact, &not_exact));
+        assert!(!is_better_candidate(&not_exact, &exact));
+    }
+
+    #[test]
+    fn global_scope_breaks_ties_on_common_prefix_len_and_exact_subnet() {
+        let global = Candidate {
+            addr: IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)),
+            common_prefix_len: 24,
+            exact_subnet: false,
+            scope: 2,
+        };
+        let link_local = Candidate {
+            addr: IpAddr::V4(Ipv4Addr::new(169, 254, 0, 1)),
+            common_prefix_len: 24,
+            exact_subnet: false,
+            scope: 1,
+        };
+
+        assert!(is_better_candidate(&global, &link_local));
+        assert!(!is_better_candidate(&link_local, &global));
+    }
 }
\ No newline at end of file
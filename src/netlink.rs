@@ -0,0 +1,227 @@
+//! Shared `rtnetlink` dump helpers used by both the Linux `getifaddrs`-based
+//! backend ([`crate::linux`]) and the Android fallback ([`crate::android`])
+//! for devices whose NDK doesn't expose `getifaddrs`.
+
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use neli::attr::Attribute;
+use neli::consts::nl::{NlmF, NlmFFlags};
+use neli::consts::rtnl::{Arphrd, Ifa, IfaFFlags, IffFlags, Ifla, RtAddrFamily, RtScope, Rtm};
+use neli::consts::socket::NlFamily;
+use neli::nl::{NlPayload, Nlmsghdr};
+use neli::rtnl::{Ifaddrmsg, Ifinfomsg};
+use neli::socket::NlSocketHandle;
+use neli::types::RtBuffer;
+
+use crate::Error;
+
+fn make_ifaddrmsg(family: RtAddrFamily) -> Ifaddrmsg {
+    Ifaddrmsg {
+        ifa_family: family,
+        ifa_prefixlen: 0,
+        ifa_flags: IfaFFlags::empty(),
+        ifa_scope: 0,
+        ifa_index: 0,
+        rtattrs: RtBuffer::new(),
+    }
+}
+
+fn make_netlink_message(ifaddrmsg: NlPayload<Ifaddrmsg>) -> Nlmsghdr<Rtm, NlPayload<Ifaddrmsg>> {
+    Nlmsghdr::new(
+        None,
+        Rtm::Getaddr,
+        NlmFFlags::new(&[NlmF::Request, NlmF::Root]),
+        None,
+        None,
+        NlPayload::Payload(ifaddrmsg),
+    )
+}
+
+/// A single address read off an `RTM_GETADDR` netlink dump, kept together
+/// with the `ifa_index`/`ifa_scope` fields needed to filter or label it
+/// afterwards.
+pub(crate) struct NetlinkAddr {
+    pub(crate) index: i32,
+    pub(crate) scope: RtScope,
+    pub(crate) label: Option<String>,
+    pub(crate) addr: IpAddr,
+}
+
+/// Performs a single `RTM_GETADDR` dump over netlink and collects every
+/// address reported for every address family, instead of stopping at the
+/// first IPv4 match. [`crate::linux::local_ip`], [`crate::linux::local_ipv6`]
+/// and [`list_afinet_netlink`] are all filters over this one dump, and the
+/// same dump backs Android's `getifaddrs`-less fallback.
+pub(crate) fn netlink_addrs() -> Result<Vec<NetlinkAddr>, Error> {
+    let mut netlink_socket = NlSocketHandle::connect(NlFamily::Route, None, &[])
+        .map_err(|err| Error::NetlinkIOError(err.to_string()))?;
+    let ifaddrmsg = make_ifaddrmsg(RtAddrFamily::Unspecified);
+    let netlink_payload = NlPayload::Payload(ifaddrmsg);
+    let netlink_message = make_netlink_message(netlink_payload);
+
+    netlink_socket
+        .send(netlink_message)
+        .map_err(|err| Error::NetlinkSendMessageError(err.to_string()))?;
+
+    let mut entries = Vec::new();
+
+    for response in netlink_socket.iter(false) {
+        let header: Nlmsghdr<_, Ifaddrmsg> =
+            response.map_err(|_| Error::NetlinkFailedToFindLocalIp)?;
+
+        if let NlPayload::Empty = header.nl_payload {
+            continue;
+        }
+
+        if header.nl_type != Rtm::Newaddr.into() {
+            return Err(Error::NetlinkFailedToFindLocalIp);
+        }
+
+        let p = header
+            .get_payload()
+            .map_err(|_| Error::NetlinkFailedToFindLocalIp)?;
+
+        // Both `Ifa::Local` and `Ifa::Address` are read into the same
+        // `addr` slot (one value per message, last attribute wins) so an
+        // `RTM_NEWADDR` that carries both for the same address is never
+        // pushed twice.
+        let mut addr = None;
+        let mut label = None;
+
+        for rtattr in p.rtattrs.iter() {
+            match (rtattr.rta_type, p.ifa_family) {
+                (Ifa::Local, RtAddrFamily::Inet) | (Ifa::Address, RtAddrFamily::Inet) => {
+                    addr = Some(IpAddr::V4(Ipv4Addr::from(u32::from_be(
+                        rtattr
+                            .get_payload_as::<u32>()
+                            .map_err(|_| Error::NetlinkFailedToFindLocalIp)?,
+                    ))));
+                }
+                (Ifa::Local, RtAddrFamily::Inet6) | (Ifa::Address, RtAddrFamily::Inet6) => {
+                    addr = Some(IpAddr::V6(Ipv6Addr::from(
+                        rtattr
+                            .get_payload_as::<[u8; 16]>()
+                            .map_err(|_| Error::NetlinkFailedToFindLocalIp)?,
+                    )));
+                }
+                (Ifa::Label, _) => {
+                    let name = rtattr
+                        .get_payload_as_with_len::<String>()
+                        .map_err(|_| Error::NetlinkFailedToFindLocalIp)?;
+
+                    label = Some(name.trim_end_matches('\0').to_string());
+                }
+                _ => {}
+            }
+        }
+
+        if let Some(addr) = addr {
+            entries.push(NetlinkAddr {
+                index: p.ifa_index,
+                scope: RtScope::from(p.ifa_scope),
+                label,
+                addr,
+            });
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Enumerates every address from a single `RTM_GETADDR` netlink dump,
+/// producing the same `Vec<(String, IpAddr)>` shape as `find_af_inet`
+/// without depending on libc's `getifaddrs` at all.
+///
+/// Interface names are read from the `Ifa::Label` attribute when the kernel
+/// includes it on the address dump; any index left unresolved falls back to
+/// a `RTM_GETLINK` dump correlated by `ifa_index`.
+pub(crate) fn list_afinet_netlink() -> Result<Vec<(String, IpAddr)>, Error> {
+    let entries = netlink_addrs()?;
+
+    let mut names: HashMap<i32, String> = HashMap::new();
+
+    for entry in &entries {
+        if let Some(label) = &entry.label {
+            names.insert(entry.index, label.clone());
+        }
+    }
+
+    if entries.iter().any(|entry| !names.contains_key(&entry.index)) {
+        for (index, name) in list_link_names()? {
+            names.entry(index).or_insert(name);
+        }
+    }
+
+    Ok(entries
+        .into_iter()
+        .map(|entry| {
+            let name = names
+                .get(&entry.index)
+                .cloned()
+                .unwrap_or_else(|| entry.index.to_string());
+
+            (name, entry.addr)
+        })
+        .collect())
+}
+
+/// Dumps `RTM_GETLINK` and builds a map of `ifa_index` to interface name,
+/// used by [`list_afinet_netlink`] to label addresses whose dump didn't
+/// carry an `Ifa::Label` attribute.
+fn list_link_names() -> Result<HashMap<i32, String>, Error> {
+    let mut netlink_socket = NlSocketHandle::connect(NlFamily::Route, None, &[])
+        .map_err(|err| Error::NetlinkIOError(err.to_string()))?;
+
+    let ifinfomsg = Ifinfomsg::new(
+        RtAddrFamily::Unspecified,
+        Arphrd::Netrom,
+        0,
+        IffFlags::empty(),
+        IffFlags::empty(),
+        RtBuffer::new(),
+    );
+    let netlink_message = Nlmsghdr::new(
+        None,
+        Rtm::Getlink,
+        NlmFFlags::new(&[NlmF::Request, NlmF::Root]),
+        None,
+        None,
+        NlPayload::Payload(ifinfomsg),
+    );
+
+    netlink_socket
+        .send(netlink_message)
+        .map_err(|err| Error::NetlinkSendMessageError(err.to_string()))?;
+
+    let mut names = HashMap::new();
+
+    for response in netlink_socket.iter(false) {
+        let header: Nlmsghdr<_, Ifinfomsg> =
+            response.map_err(|_| Error::NetlinkFailedToFindLocalIp)?;
+
+        if let NlPayload::Empty = header.nl_payload {
+            continue;
+        }
+
+        if header.nl_type != Rtm::Newlink.into() {
+            continue;
+        }
+
+        let p = header
+            .get_payload()
+            .map_err(|_| Error::NetlinkFailedToFindLocalIp)?;
+
+        for rtattr in p.rtattrs.iter() {
+            if rtattr.rta_type == Ifla::Ifname {
+                let name = rtattr
+                    .get_payload_as_with_len::<String>()
+                    .map_err(|_| Error::NetlinkFailedToFindLocalIp)?;
+
+                names.insert(p.ifi_index, name.trim_end_matches('\0').to_string());
+            }
+        }
+    }
+
+    Ok(names)
+}